@@ -0,0 +1,110 @@
+//! A classified auth failure, so a frame-based caller can close the
+//! websocket with a specific status code instead of an opaque disconnect.
+
+use std::fmt;
+
+use actix::Actor;
+use actix_web_actors::ws::{self, CloseCode, CloseReason};
+
+use crate::actix_web::error::ErrorUnauthorized;
+
+/// Why `AuthMode::validate` rejected a connection. The variant is what lets
+/// [`AuthError::close_reason`] pick a websocket close code that tells the
+/// peer something actionable, instead of just dropping the connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthError {
+    /// A required header, frame field, or claim was absent.
+    MissingField(String),
+    /// The field was present but couldn't be parsed or decoded.
+    InvalidField(String),
+    /// The signature didn't verify against the key material presented.
+    BadSignature,
+    /// The nonce did not strictly advance the last one seen for this key.
+    StaleNonce,
+    /// The token's own `exp`/`nbf` claims say it isn't currently valid.
+    Expired,
+    /// Any other rejection (e.g. a missing scope, an unreachable
+    /// introspection endpoint) that doesn't warrant its own close code.
+    Other(String),
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingField(field) => write!(f, "\"{}\" not found", field),
+            Self::InvalidField(field) => write!(f, "invalid \"{}\"", field),
+            Self::BadSignature => f.write_str("signature mismatch"),
+            Self::StaleNonce => f.write_str("nonce must be strictly greater than the last seen nonce"),
+            Self::Expired => f.write_str("token is expired or not yet valid"),
+            Self::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+impl AuthError {
+    /// The close code/reason an actor should hand to `ws::WebsocketContext::close`.
+    /// Uses the private-use range `4401`/`4408` (RFC 6455 reserves `4000..=4999`
+    /// for applications) so a client can tell "unauthorized" from "expired"
+    /// instead of seeing an opaque disconnect.
+    pub fn close_reason(&self) -> CloseReason {
+        let code = match self {
+            Self::Expired => 4408,
+            Self::MissingField(_) | Self::InvalidField(_) | Self::BadSignature | Self::StaleNonce | Self::Other(_) => {
+                4401
+            }
+        };
+        CloseReason {
+            code: CloseCode::Other(code),
+            description: Some(self.to_string()),
+        }
+    }
+
+    /// Closes `ctx` with this error's [`close_reason`](Self::close_reason).
+    /// Call this from a `StreamHandler` frame handler wherever
+    /// `AuthMode::validate_frame` returns `Err`, so the peer gets an
+    /// actionable Close frame instead of a dropped connection.
+    pub fn close<A>(&self, ctx: &mut ws::WebsocketContext<A>)
+    where
+        A: Actor<Context = ws::WebsocketContext<A>>,
+    {
+        ctx.close(Some(self.close_reason()));
+    }
+}
+
+/// The HTTP-upgrade path still rejects with a plain `ActixResult`; frame
+/// validation goes through [`AuthError`] directly so the actor can build a
+/// structured `Close` frame instead.
+impl From<AuthError> for crate::actix_web::Error {
+    fn from(error: AuthError) -> Self {
+        ErrorUnauthorized(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::AuthError;
+    use actix_web_actors::ws::CloseCode;
+
+    fn code_of(error: AuthError) -> u16 {
+        match error.close_reason().code {
+            CloseCode::Other(code) => code,
+            _ => panic!("expected a CloseCode::Other"),
+        }
+    }
+
+    #[test]
+    fn expired_maps_to_the_expired_code() {
+        assert_eq!(4408, code_of(AuthError::Expired));
+    }
+
+    #[test]
+    fn every_other_variant_maps_to_the_unauthorized_code() {
+        assert_eq!(4401, code_of(AuthError::MissingField("field".to_string())));
+        assert_eq!(4401, code_of(AuthError::InvalidField("field".to_string())));
+        assert_eq!(4401, code_of(AuthError::BadSignature));
+        assert_eq!(4401, code_of(AuthError::StaleNonce));
+        assert_eq!(4401, code_of(AuthError::Other("reason".to_string())));
+    }
+}
@@ -0,0 +1,53 @@
+use std::collections::HashSet;
+
+/// What a successful [`AuthMode::validate`](super::AuthMode::validate) call
+/// proved about the caller: who they are, who vouched for them, and what
+/// they're allowed to do. Handlers use this to authorize individual
+/// messages instead of treating "connected" and "authorized" as the same
+/// thing.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AuthenticatedUser {
+    pub subject: String,
+    pub issuer: Option<String>,
+    /// The OAuth2 client the token was issued to (introspection's
+    /// `client_id`), i.e. the relying party — not to be confused with
+    /// `issuer`, which is who vouched for the token.
+    pub client_id: Option<String>,
+    pub scope: HashSet<String>,
+}
+
+impl AuthenticatedUser {
+    pub fn new(subject: impl Into<String>) -> Self {
+        Self {
+            subject: subject.into(),
+            issuer: None,
+            client_id: None,
+            scope: HashSet::new(),
+        }
+    }
+
+    pub fn with_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    pub fn with_client_id(mut self, client_id: impl Into<String>) -> Self {
+        self.client_id = Some(client_id.into());
+        self
+    }
+
+    pub fn with_scope(mut self, scope: HashSet<String>) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Parses the space-delimited `scope` claim format used by OAuth2/OIDC
+    /// (`RFC 6749 §3.3`) into a set of individual scope strings.
+    pub fn parse_scope(scope_claim: &str) -> HashSet<String> {
+        scope_claim.split_whitespace().map(str::to_owned).collect()
+    }
+
+    pub fn has_scopes(&self, required: &[&str]) -> bool {
+        required.iter().all(|s| self.scope.contains(*s))
+    }
+}
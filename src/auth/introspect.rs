@@ -0,0 +1,65 @@
+//! Delegated verification for `AuthMode::Introspect`: forward the presented
+//! bearer token to a remote introspection endpoint and trust its verdict,
+//! following the token-introspection pattern used by OAuth2/OIDC token
+//! endpoints (`RFC 7662`) and IndieAuth token verification.
+
+use serde::Deserialize;
+use url::Url;
+
+use super::{AuthError, AuthenticatedUser};
+
+/// The subset of an introspection response this crate understands. IndieAuth
+/// issuers return `me`; OAuth2 token introspection returns `sub` — both are
+/// accepted as the identity field.
+#[derive(Deserialize)]
+struct IntrospectionResponse {
+    active: bool,
+    sub: Option<String>,
+    me: Option<String>,
+    client_id: Option<String>,
+    #[serde(default)]
+    scope: String,
+}
+
+impl IntrospectionResponse {
+    fn into_user(self) -> Result<AuthenticatedUser, AuthError> {
+        if !self.active {
+            return Err(AuthError::Other("token is not active".to_string()));
+        }
+        let subject = self
+            .sub
+            .or(self.me)
+            .ok_or_else(|| AuthError::MissingField("sub\"/\"me".to_string()))?;
+
+        let mut user = AuthenticatedUser::new(subject).with_scope(AuthenticatedUser::parse_scope(&self.scope));
+        if let Some(client_id) = self.client_id {
+            user = user.with_client_id(client_id);
+        }
+        Ok(user)
+    }
+}
+
+/// Forwards `token` to the introspection `endpoint`, optionally
+/// authenticating the introspection call itself with a static
+/// `Authorization` header value (e.g. HTTP Basic client credentials).
+pub(crate) async fn introspect(
+    endpoint: &Url,
+    authorization: Option<&str>,
+    token: &str,
+) -> Result<AuthenticatedUser, AuthError> {
+    let client = reqwest::Client::new();
+    let mut request = client.post(endpoint.clone()).form(&[("token", token)]);
+    if let Some(authorization) = authorization {
+        request = request.header(reqwest::header::AUTHORIZATION, authorization);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| AuthError::Other(e.to_string()))?
+        .json::<IntrospectionResponse>()
+        .await
+        .map_err(|e| AuthError::Other(e.to_string()))?;
+
+    response.into_user()
+}
@@ -0,0 +1,87 @@
+//! Where to find the credential on an incoming request: an HTTP header on the
+//! upgrade request, or a field inside the first websocket frame.
+
+#[derive(Clone, Copy)]
+pub enum AuthLocation<'a> {
+    Header(AuthHeader<'a>),
+    /// A named cookie in the `Cookie` request header, e.g. for a browser
+    /// client that keeps its session JWT in a cookie jar rather than
+    /// attaching it as an `Authorization` header.
+    Cookie { name: &'a str },
+    WebSocketFrame(AuthField<'a>),
+}
+
+impl<'a> From<AuthHeader<'a>> for AuthLocation<'a> {
+    fn from(header: AuthHeader<'a>) -> Self {
+        Self::Header(header)
+    }
+}
+
+impl<'a> From<AuthField<'a>> for AuthLocation<'a> {
+    fn from(field: AuthField<'a>) -> Self {
+        Self::WebSocketFrame(field)
+    }
+}
+
+/// An HTTP header whose value is a template such as `"Bearer {token}"`; the
+/// `{token}` marker is stripped on extraction, with whatever comes before and
+/// after kept as the expected prefix/suffix.
+#[derive(Clone, Copy)]
+pub struct AuthHeader<'a> {
+    pub(crate) field: &'a str,
+    pub(crate) token_bound: (Option<&'a str>, Option<&'a str>),
+}
+
+impl<'a> AuthHeader<'a> {
+    const MARKER: &'static str = "{token}";
+
+    /// Builds a header template from its field name and value pattern, e.g.
+    /// `AuthHeader::new("Authorization", "Bearer {token}")`. Returns `None`
+    /// if `template` does not contain the `{token}` marker.
+    pub fn new(field: &'a str, template: &'a str) -> Option<Self> {
+        let marker_at = template.find(Self::MARKER)?;
+        let (prefix, suffix) = template.split_at(marker_at);
+        let suffix = &suffix[Self::MARKER.len()..];
+        Some(Self {
+            field,
+            token_bound: (
+                (!prefix.is_empty()).then_some(prefix),
+                (!suffix.is_empty()).then_some(suffix),
+            ),
+        })
+    }
+}
+
+/// Names of the fields carrying auth data inside a websocket frame (a JSON
+/// object). `key_or_token` holds either the bearer token (JWT mode) or the
+/// API key identifier (API-key mode); `sign`, `payload` and `nonce` only
+/// apply to the API-key mode.
+#[derive(Clone, Copy)]
+pub struct AuthField<'a> {
+    pub(crate) sign: Option<&'a str>,
+    pub(crate) key_or_token: &'a str,
+    pub(crate) payload: Option<&'a str>,
+    pub(crate) nonce: Option<&'a str>,
+}
+
+impl<'a> AuthField<'a> {
+    /// A frame field carrying only a bearer token, as used by `AuthMode::JWT`.
+    pub fn token(field: &'a str) -> Self {
+        Self {
+            sign: None,
+            key_or_token: field,
+            payload: None,
+            nonce: None,
+        }
+    }
+
+    /// Frame fields carrying a signed API-key request, as used by `AuthMode::APIKey`.
+    pub fn apikey(key: &'a str, sign: &'a str, payload: &'a str, nonce: &'a str) -> Self {
+        Self {
+            sign: Some(sign),
+            key_or_token: key,
+            payload: Some(payload),
+            nonce: Some(nonce),
+        }
+    }
+}
@@ -1,13 +1,21 @@
 pub(super) use crate::actix_web::Result as ActixResult;
-use crate::actix_web::{error::ErrorUnauthorized, http::HeaderMap};
+use crate::actix_web::http::HeaderMap;
 use serde_json as json;
+use std::borrow::Cow;
 use std::sync::Arc;
 
 pub mod apikey;
+mod error;
+mod identity;
+mod introspect;
 pub mod jwt;
 mod location;
+pub mod nonce;
 
+pub use error::AuthError;
+pub use identity::*;
 pub use location::*;
+pub use url::Url;
 
 type WsRequest = json::Value;
 pub(crate) enum AuthRequest<'a> {
@@ -31,14 +39,22 @@ impl From<WsRequest> for AuthRequest<'_> {
 pub enum AuthMode<'a> {
     JWT {
         auth_location: AuthLocation<'a>,
-        signing_secret: &'a [u8],
+        verification_key: jwt::VerificationKey<'a>,
         validate: jwt::ClaimCode,
     },
     APIKey {
         auth_field: AuthField<'a>,
         signing_secret: &'a [u8],
         uri_path: &'a str,
-        last_nonce_getter: Arc<dyn Fn(&str) -> Option<u64> + Sync + Send>,
+        nonce_store: Arc<dyn nonce::NonceStore>,
+    },
+    /// Delegates verification to a remote token-introspection endpoint
+    /// instead of checking a signature locally; only reachable through
+    /// [`AuthMode::validate_async`].
+    Introspect {
+        auth_location: AuthLocation<'a>,
+        endpoint: Url,
+        authorization: Option<&'a str>,
     },
     None,
 }
@@ -55,77 +71,122 @@ impl AuthMode<'_> {
         Self::JWT {
             auth_location: AuthLocation::from(auth_header),
             validate: jwt::ClaimCode::disable_all(),
-            signing_secret,
+            verification_key: jwt::VerificationKey::hmac(signing_secret),
         }
     }
 
-    pub(crate) fn validate(&self, request: AuthRequest) -> ActixResult<()> {
+    pub(crate) fn validate(&self, request: AuthRequest) -> ActixResult<AuthenticatedUser> {
+        self.validate_typed(request).map_err(Into::into)
+    }
+
+    /// Validates a websocket frame and keeps the failure classified as an
+    /// [`AuthError`] instead of flattening it into an actix HTTP error, so
+    /// the actor's frame-handling code can close with a specific
+    /// `ws::CloseCode` (see [`AuthError::close`]) rather than just dropping
+    /// the connection.
+    pub(crate) fn validate_frame(&self, payload: json::Value) -> Result<AuthenticatedUser, AuthError> {
+        self.validate_typed(AuthRequest::WebsocketFrame(payload))
+    }
+
+    fn validate_typed(&self, request: AuthRequest) -> Result<AuthenticatedUser, AuthError> {
         match self {
-            Self::None => Ok(()),
+            Self::None => Ok(AuthenticatedUser::default()),
             Self::JWT {
                 auth_location: template,
                 validate: claim_code,
-                signing_secret: secret,
-            } => {
-                let token = match (template, &request) {
-                    (AuthLocation::Header(template), AuthRequest::HttpHeader(headers)) => {
-                        extract_token_from_header(template, headers)?
-                    }
-                    (AuthLocation::WebSocketFrame(field), AuthRequest::WebsocketFrame(payload)) => {
-                        extract_token_from_wsframe(field.key_or_token, payload)?
-                    }
-                    _ => unreachable!("check your `ws_upgrader` or `Actor::handler` implementation"),
-                };
-                claim_code.validate(secret, token)
-            }
+                verification_key,
+            } => claim_code.validate(verification_key, extract_token(template, &request)?.as_ref()),
+            Self::Introspect { .. } => Err(AuthError::Other(
+                "AuthMode::Introspect requires an HTTP round-trip; call `validate_async` instead".to_string(),
+            )),
             Self::APIKey {
                 auth_field,
                 uri_path,
-                last_nonce_getter: get_nonce_from,
+                nonce_store,
                 signing_secret,
             } => {
                 let AuthField {
                     sign: signature_field,
                     key_or_token: key_field,
                     payload: payload_field,
+                    nonce: nonce_field,
                 } = auth_field;
                 if let AuthRequest::WebsocketFrame(payload) = request {
                     let signature_field = signature_field.expect("AuthField::apikey");
                     let payload_field = payload_field.expect("AuthField::apikey");
+                    let nonce_field = nonce_field.expect("AuthField::apikey");
                     let get_payload_from = |i: &str| {
                         payload
                             .get(i)
                             .and_then(|v| v.as_str())
-                            .ok_or_else(|| ErrorUnauthorized(format!("\"{}\" not found", i)))
+                            .ok_or_else(|| AuthError::MissingField(i.to_string()))
                     };
 
                     let (apikey, signature) = (get_payload_from(key_field)?, get_payload_from(signature_field)?);
+                    let nonce = get_payload_from(nonce_field)?
+                        .parse::<u64>()
+                        .map_err(|_| AuthError::InvalidField(nonce_field.to_string()))?;
                     let data = apikey::Data {
                         uri_path: uri_path.to_string(),
-                        nonce: get_nonce_from(apikey)
-                            .ok_or_else(|| ErrorUnauthorized(format!("invalid \"{}\"", key_field)))?,
+                        nonce,
                         payload: payload
                             .get(payload_field)
                             .cloned()
-                            .ok_or_else(|| ErrorUnauthorized(format!("\"{}\" not found", payload_field)))?,
+                            .ok_or_else(|| AuthError::MissingField(payload_field.to_string()))?,
                     };
 
-                    data.validate(signing_secret.to_vec(), signature.as_bytes())
+                    data.validate(apikey, nonce_store.as_ref(), signing_secret.to_vec(), signature.as_bytes())
                 } else {
                     unreachable!("check your `Actor::handler` implementation")
                 }
             }
         }
     }
+
+    /// Like [`AuthMode::validate`], but also supports [`AuthMode::Introspect`],
+    /// which needs an HTTP round-trip to the configured endpoint. The
+    /// websocket upgrader should call this instead of `validate` so a single
+    /// code path handles every mode.
+    pub(crate) async fn validate_async(&self, request: AuthRequest<'_>) -> ActixResult<AuthenticatedUser> {
+        self.validate_async_typed(request).await.map_err(Into::into)
+    }
+
+    async fn validate_async_typed(&self, request: AuthRequest<'_>) -> Result<AuthenticatedUser, AuthError> {
+        let Self::Introspect {
+            auth_location: template,
+            endpoint,
+            authorization,
+        } = self
+        else {
+            return self.validate_typed(request);
+        };
+
+        let token = extract_token(template, &request)?;
+        introspect::introspect(endpoint, *authorization, token.as_ref()).await
+    }
 }
 
-fn extract_token_from_header<'a>(template: &AuthHeader, header: &'a HeaderMap) -> ActixResult<&'a str> {
-    let header_value = header.get(template.field).ok_or_else(|| {
-        let message = ["Missing field '", template.field, "'"].concat();
-        ErrorUnauthorized(message)
-    })?;
+fn extract_token<'a>(template: &AuthLocation, request: &'a AuthRequest) -> Result<Cow<'a, str>, AuthError> {
+    match (template, request) {
+        (AuthLocation::Header(template), AuthRequest::HttpHeader(headers)) => {
+            extract_token_from_header(template, headers).map(Cow::Borrowed)
+        }
+        (AuthLocation::Cookie { name }, AuthRequest::HttpHeader(headers)) => extract_token_from_cookie(name, headers),
+        (AuthLocation::WebSocketFrame(field), AuthRequest::WebsocketFrame(payload)) => {
+            extract_token_from_wsframe(field.key_or_token, payload).map(Cow::Borrowed)
+        }
+        _ => unreachable!("check your `ws_upgrader` or `Actor::handler` implementation"),
+    }
+}
 
-    let mut token = header_value.to_str().map_err(|e| ErrorUnauthorized(e.to_string()))?;
+fn extract_token_from_header<'a>(template: &AuthHeader, header: &'a HeaderMap) -> Result<&'a str, AuthError> {
+    let header_value = header
+        .get(template.field)
+        .ok_or_else(|| AuthError::MissingField(template.field.to_string()))?;
+
+    let mut token = header_value
+        .to_str()
+        .map_err(|_| AuthError::InvalidField(template.field.to_string()))?;
     if let Some(non_token) = template.token_bound.0 {
         token = token.trim_start_matches(non_token);
     }
@@ -135,13 +196,35 @@ fn extract_token_from_header<'a>(template: &AuthHeader, header: &'a HeaderMap) -
     Ok(token)
 }
 
-fn extract_token_from_wsframe<'a>(field: &str, dataframe: &'a json::Value) -> ActixResult<&'a str> {
+/// Parses the `Cookie` request header (`name=value; name2=value2`) and
+/// returns the URL-decoded value of `name`.
+fn extract_token_from_cookie<'a>(name: &str, header: &'a HeaderMap) -> Result<Cow<'a, str>, AuthError> {
+    let cookie_header = header
+        .get("Cookie")
+        .ok_or_else(|| AuthError::MissingField("Cookie".to_string()))?
+        .to_str()
+        .map_err(|_| AuthError::InvalidField("Cookie".to_string()))?;
+
+    cookie_header
+        .split(';')
+        .map(str::trim)
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(cookie_name, _)| *cookie_name == name)
+        .ok_or_else(|| AuthError::MissingField(name.to_string()))
+        .and_then(|(_, value)| {
+            percent_encoding::percent_decode_str(value)
+                .decode_utf8()
+                .map_err(|_| AuthError::InvalidField(name.to_string()))
+        })
+}
+
+fn extract_token_from_wsframe<'a>(field: &str, dataframe: &'a json::Value) -> Result<&'a str, AuthError> {
     match dataframe {
         json::Value::Object(obj) => obj
             .get(field)
             .and_then(|s| s.as_str())
-            .ok_or_else(|| ErrorUnauthorized(format!("\"{}\" not found or it's not a `string`", field))),
-        _ => Err(ErrorUnauthorized("request must be in type object")),
+            .ok_or_else(|| AuthError::MissingField(field.to_string())),
+        _ => Err(AuthError::InvalidField("request body".to_string())),
     }
 }
 
@@ -164,13 +247,25 @@ mod unit_tests {
         const TOKEN: &str = include_str!("../../test/fixture/jwt_token.key");
 
         let auth_header = AuthHeader::new("Authorization", "Bearer {token}").expect("has {token}");
+        let template = AuthLocation::from(auth_header);
         let mut request_header = HeaderMap::new();
 
         request_header.insert("API-Key".parse()?, "12345".parse()?);
         request_header.insert("Authorization".parse()?, ["Bearer ", TOKEN].concat().parse()?);
 
-        assert_eq!(TOKEN, extract_token(&auth_header, &request_header)?);
-        assert!(extract_token(&auth_header, &HeaderMap::new()).is_err());
+        assert_eq!(TOKEN, extract_token(&template, &AuthRequest::from(&request_header))?);
+        assert!(extract_token(&template, &AuthRequest::from(&HeaderMap::new())).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_token_from_cookie() -> Result<(), Box<dyn Error>> {
+        let mut header = HeaderMap::new();
+        header.insert("Cookie".parse()?, "session=abc%20123; other=ignored".parse()?);
+
+        assert_eq!("abc 123", extract_token_from_cookie("session", &header)?);
+        assert!(extract_token_from_cookie("missing", &header).is_err());
+        assert!(extract_token_from_cookie("session", &HeaderMap::new()).is_err());
         Ok(())
     }
 }
@@ -0,0 +1,99 @@
+//! Per-key nonce tracking for `AuthMode::APIKey`, so a signed frame can be
+//! used at most once.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+
+/// Tracks the last nonce seen for each API key and lets a new one be
+/// committed only if it advances the counter. Implementations must make
+/// `commit_nonce` atomic with respect to itself (e.g. a single lock holding
+/// both the check and the write) so two concurrent requests can't both
+/// commit the same or an out-of-order nonce.
+pub trait NonceStore: Sync + Send {
+    fn last_nonce(&self, key: &str) -> Option<u64>;
+    fn commit_nonce(&self, key: &str, nonce: u64) -> Result<(), NonceError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonceError(String);
+
+impl fmt::Display for NonceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for NonceError {}
+
+/// A plain `Fn(&str) -> Option<u64>` is still a valid, read-only
+/// `NonceStore`: it reports the last nonce it knows about but has nowhere to
+/// persist a new one, so `commit_nonce` is a no-op. This form predates
+/// per-key commits and does not, by itself, close the replay window —
+/// use [`InMemoryNonceStore`] (or your own stateful `NonceStore`) for that.
+impl<F> NonceStore for F
+where
+    F: Fn(&str) -> Option<u64> + Sync + Send + ?Sized,
+{
+    fn last_nonce(&self, key: &str) -> Option<u64> {
+        self(key)
+    }
+
+    fn commit_nonce(&self, _key: &str, _nonce: u64) -> Result<(), NonceError> {
+        Ok(())
+    }
+}
+
+/// An in-process `NonceStore` backed by a `Mutex<HashMap>`. Good enough for
+/// a single instance; a multi-instance deployment needs a shared store
+/// (e.g. Redis) behind the same trait.
+#[derive(Default)]
+pub struct InMemoryNonceStore(Mutex<HashMap<String, u64>>);
+
+impl InMemoryNonceStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl NonceStore for InMemoryNonceStore {
+    fn last_nonce(&self, key: &str) -> Option<u64> {
+        self.0.lock().expect("nonce store mutex poisoned").get(key).copied()
+    }
+
+    fn commit_nonce(&self, key: &str, nonce: u64) -> Result<(), NonceError> {
+        let mut nonces = self.0.lock().expect("nonce store mutex poisoned");
+        if nonces.get(key).is_some_and(|&last| nonce <= last) {
+            return Err(NonceError(format!("nonce {} is not greater than the last committed nonce", nonce)));
+        }
+        nonces.insert(key.to_string(), nonce);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::{InMemoryNonceStore, NonceStore};
+
+    #[test]
+    fn commit_nonce_advances_last_nonce() {
+        let store = InMemoryNonceStore::new();
+        assert_eq!(None, store.last_nonce("key"));
+
+        store.commit_nonce("key", 1).expect("first commit");
+        assert_eq!(Some(1), store.last_nonce("key"));
+
+        store.commit_nonce("key", 2).expect("strictly greater commit");
+        assert_eq!(Some(2), store.last_nonce("key"));
+    }
+
+    #[test]
+    fn commit_nonce_rejects_a_second_commit_at_the_same_nonce() {
+        let store = InMemoryNonceStore::new();
+        store.commit_nonce("key", 5).expect("first commit");
+
+        assert!(store.commit_nonce("key", 5).is_err());
+        assert!(store.commit_nonce("key", 4).is_err());
+        assert_eq!(Some(5), store.last_nonce("key"));
+    }
+}
@@ -0,0 +1,147 @@
+//! Mints JWTs whose claims line up exactly with what
+//! [`crate::auth::jwt::ClaimCode`] validates.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use serde::Serialize;
+
+use crate::actix_web::error::ErrorInternalServerError;
+
+use super::ActixResult;
+
+#[derive(Serialize)]
+struct Claims<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    iss: Option<&'a str>,
+    sub: &'a str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    aud: Vec<&'a str>,
+    iat: u64,
+    exp: u64,
+    scope: &'a str,
+}
+
+/// Signs claims with a fixed header/algorithm and a short default validity.
+pub struct JwtIssuer {
+    header: Header,
+    encoding_key: EncodingKey,
+    issuer: Option<String>,
+    validity: Duration,
+}
+
+impl JwtIssuer {
+    const DEFAULT_VALIDITY: Duration = Duration::from_secs(15 * 60);
+
+    pub fn new(encoding_key: EncodingKey, algorithm: Algorithm) -> Self {
+        Self {
+            header: Header::new(algorithm),
+            encoding_key,
+            issuer: None,
+            validity: Self::DEFAULT_VALIDITY,
+        }
+    }
+
+    pub fn hmac(secret: &[u8], algorithm: Algorithm) -> Self {
+        Self::new(EncodingKey::from_secret(secret), algorithm)
+    }
+
+    pub fn rsa_pem(pem: &[u8], algorithm: Algorithm) -> ActixResult<Self> {
+        EncodingKey::from_rsa_pem(pem)
+            .map(|key| Self::new(key, algorithm))
+            .map_err(|e| ErrorInternalServerError(e.to_string()))
+    }
+
+    pub fn ec_pem(pem: &[u8], algorithm: Algorithm) -> ActixResult<Self> {
+        EncodingKey::from_ec_pem(pem)
+            .map(|key| Self::new(key, algorithm))
+            .map_err(|e| ErrorInternalServerError(e.to_string()))
+    }
+
+    pub fn with_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    pub fn with_validity(mut self, validity: Duration) -> Self {
+        self.validity = validity;
+        self
+    }
+
+    /// Issues a signed, compact JWT for `subject`, granting `scope`, valid
+    /// from now through this issuer's configured validity `Duration`.
+    /// `audience` is carried as the `aud` claim when non-empty, but
+    /// `auth::jwt::ClaimCode::validate` does not check it, so omitting it is
+    /// just as acceptable to this crate's own validator.
+    pub fn issue(&self, subject: &str, audience: &[&str], scope: &[&str]) -> ActixResult<String> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| ErrorInternalServerError(e.to_string()))?;
+        let scope = scope.join(" ");
+
+        let claims = Claims {
+            iss: self.issuer.as_deref(),
+            sub: subject,
+            aud: audience.to_vec(),
+            iat: now.as_secs(),
+            exp: (now + self.validity).as_secs(),
+            scope: &scope,
+        };
+
+        jsonwebtoken::encode(&self.header, &claims, &self.encoding_key).map_err(|e| ErrorInternalServerError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use jsonwebtoken::Algorithm;
+
+    use super::JwtIssuer;
+    use crate::auth::jwt::{ClaimCode, VerificationKey};
+
+    #[test]
+    fn issued_jwt_round_trips_through_claim_code_validate() {
+        let secret = b"test-secret";
+        let issuer = JwtIssuer::hmac(secret, Algorithm::HS256).with_issuer("https://issuer.example");
+
+        let token = issuer
+            .issue("user-123", &["my-audience"], &["read", "write"])
+            .expect("issue");
+
+        let user = ClaimCode::disable_all()
+            .require_exp()
+            .require_scopes(&["read"])
+            .validate(&VerificationKey::hmac(secret), &token)
+            .expect("validate");
+
+        assert_eq!("user-123", user.subject);
+        assert_eq!(Some("https://issuer.example".to_string()), user.issuer);
+        assert!(user.scope.contains("write"));
+    }
+
+    #[test]
+    fn issued_jwt_without_audience_still_validates() {
+        let secret = b"test-secret";
+        let issuer = JwtIssuer::hmac(secret, Algorithm::HS256);
+
+        let token = issuer.issue("user-123", &[], &[]).expect("issue");
+
+        ClaimCode::disable_all()
+            .validate(&VerificationKey::hmac(secret), &token)
+            .expect("validate");
+    }
+
+    #[test]
+    fn issue_supports_multiple_audiences() {
+        let secret = b"test-secret";
+        let issuer = JwtIssuer::hmac(secret, Algorithm::HS256);
+
+        let token = issuer
+            .issue("user-123", &["audience-a", "audience-b"], &[])
+            .expect("issue");
+
+        ClaimCode::disable_all()
+            .validate(&VerificationKey::hmac(secret), &token)
+            .expect("validate");
+    }
+}
@@ -0,0 +1,11 @@
+//! Mints the JWTs and API-key signatures that [`crate::auth::AuthMode`]
+//! validates, kept in the same crate so issuing and validating claim
+//! formats can't drift apart.
+
+pub(super) use crate::actix_web::Result as ActixResult;
+
+mod apikey;
+mod jwt;
+
+pub use apikey::ApiKeySigner;
+pub use jwt::JwtIssuer;